@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Mutex;
+
+use super::{Checkpointer, CheckpointerError};
+
+/// The direction in which a monitored metric should improve when ranking checkpoints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointingMode {
+    /// Lower values are better (e.g. loss).
+    Min,
+    /// Higher values are better (e.g. accuracy).
+    Max,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    epoch: usize,
+    ranked_value: f64,
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ranked_value
+            .partial_cmp(&other.ranked_value)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Wraps a [checkpointer](Checkpointer) and keeps only the `num_keep` best checkpoints, ranked by
+/// a metric value registered for each epoch, deleting the worst kept checkpoint once the bound is
+/// exceeded instead of always deleting the oldest one.
+pub struct BestFileCheckpointer<C> {
+    checkpointer: C,
+    mode: CheckpointingMode,
+    num_keep: usize,
+    kept: Mutex<BinaryHeap<Entry>>,
+}
+
+impl<C> BestFileCheckpointer<C> {
+    /// Creates a new checkpointer that keeps the `num_keep` best checkpoints according to `mode`.
+    pub fn new(checkpointer: C, mode: CheckpointingMode, num_keep: usize) -> Self {
+        Self {
+            checkpointer,
+            mode,
+            num_keep,
+            kept: Mutex::new(BinaryHeap::new()),
+        }
+    }
+
+    /// Registers the metric value observed for `epoch` and returns the epoch that should be
+    /// evicted, if the number of kept checkpoints now exceeds `num_keep`.
+    ///
+    /// The heap always orders its maximum as the worst ranked checkpoint, regardless of `mode`,
+    /// so the value pushed for [CheckpointingMode::Max] is negated before insertion.
+    pub fn register(&self, epoch: usize, value: f64) -> Option<usize> {
+        let ranked_value = match self.mode {
+            CheckpointingMode::Min => value,
+            CheckpointingMode::Max => -value,
+        };
+
+        let mut kept = self.kept.lock().unwrap();
+        kept.push(Entry { epoch, ranked_value });
+
+        if kept.len() > self.num_keep {
+            kept.pop().map(|worst| worst.epoch)
+        } else {
+            None
+        }
+    }
+}
+
+impl<C, T> Checkpointer<T> for BestFileCheckpointer<C>
+where
+    C: Checkpointer<T>,
+{
+    fn save(&self, epoch: usize, record: T) -> Result<(), CheckpointerError> {
+        self.checkpointer.save(epoch, record)
+    }
+
+    fn restore(&self, epoch: usize) -> Result<T, CheckpointerError> {
+        self.checkpointer.restore(epoch)
+    }
+
+    fn delete(&self, epoch: usize) -> Result<(), CheckpointerError> {
+        self.checkpointer.delete(epoch)
+    }
+}
+
+/// Type-erased handle to [BestFileCheckpointer::register], independent of the record type being
+/// checkpointed, so the learner's epoch loop can rank and evict checkpoints without needing to
+/// know what's actually being saved.
+pub trait BestCheckpointerHandle: Send + Sync {
+    /// Registers the metric value observed for `epoch` and returns the epoch to evict, if any.
+    fn register(&self, epoch: usize, value: f64) -> Option<usize>;
+    /// Deletes the checkpoint saved for `epoch`.
+    fn delete(&self, epoch: usize);
+}
+
+impl<C, T> BestCheckpointerHandle for BestFileCheckpointer<C>
+where
+    C: Checkpointer<T> + Send + Sync,
+    T: Send + Sync,
+{
+    fn register(&self, epoch: usize, value: f64) -> Option<usize> {
+        BestFileCheckpointer::register(self, epoch, value)
+    }
+
+    fn delete(&self, epoch: usize) {
+        let _ = Checkpointer::delete(self, epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpointer(mode: CheckpointingMode, num_keep: usize) -> BestFileCheckpointer<()> {
+        BestFileCheckpointer::new((), mode, num_keep)
+    }
+
+    #[test]
+    fn no_eviction_while_under_the_limit() {
+        let checkpointer = checkpointer(CheckpointingMode::Min, 2);
+
+        assert_eq!(checkpointer.register(1, 1.0), None);
+        assert_eq!(checkpointer.register(2, 2.0), None);
+    }
+
+    #[test]
+    fn min_mode_evicts_the_worst_ranked_epoch_once_over_the_limit() {
+        let checkpointer = checkpointer(CheckpointingMode::Min, 2);
+
+        assert_eq!(checkpointer.register(1, 1.0), None);
+        assert_eq!(checkpointer.register(2, 2.0), None);
+        // Epoch 2 has the highest (worst) loss, so it's the one evicted.
+        assert_eq!(checkpointer.register(3, 0.5), Some(2));
+    }
+
+    #[test]
+    fn max_mode_evicts_the_worst_ranked_epoch_once_over_the_limit() {
+        let checkpointer = checkpointer(CheckpointingMode::Max, 2);
+
+        assert_eq!(checkpointer.register(1, 0.8), None);
+        assert_eq!(checkpointer.register(2, 0.5), None);
+        // Epoch 2 has the lowest (worst) accuracy, so it's the one evicted.
+        assert_eq!(checkpointer.register(3, 0.9), Some(2));
+    }
+
+    #[test]
+    fn repeated_eviction_keeps_only_the_best_num_keep_entries() {
+        let checkpointer = checkpointer(CheckpointingMode::Min, 1);
+
+        assert_eq!(checkpointer.register(1, 3.0), None);
+        assert_eq!(checkpointer.register(2, 2.0), Some(1));
+        assert_eq!(checkpointer.register(3, 1.0), Some(2));
+    }
+}
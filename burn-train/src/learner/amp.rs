@@ -0,0 +1,121 @@
+use burn_core::module::{Module, ModuleVisitor, ParamId};
+use burn_core::optim::GradientsParams;
+use burn_core::tensor::backend::Backend;
+use burn_core::tensor::Tensor;
+
+/// Dynamic loss scaler used for mixed-precision training.
+///
+/// The loss is multiplied by [scale](Self::scale) before the backward pass so that small
+/// gradients do not underflow in reduced precision. Before the optimizer step, the accumulated
+/// gradients must be unscaled by the same factor and checked for non-finite values: if any are
+/// found, the step is skipped and the scale is halved via [update](Self::update); otherwise, once
+/// `growth_interval` consecutive steps have succeeded, the scale is doubled, capped at
+/// `max_scale`.
+pub struct GradScaler {
+    scale: f64,
+    growth_interval: usize,
+    max_scale: f64,
+    successful_steps: usize,
+}
+
+impl GradScaler {
+    /// Creates a new gradient scaler.
+    ///
+    /// # Arguments
+    ///
+    /// * `init_scale` - The initial scale, e.g. `2^16`.
+    /// * `growth_interval` - The number of consecutive successful steps before the scale grows.
+    /// * `max_scale` - The upper bound the scale is never allowed to exceed.
+    pub fn new(init_scale: f64, growth_interval: usize, max_scale: f64) -> Self {
+        Self {
+            scale: init_scale,
+            growth_interval,
+            max_scale,
+            successful_steps: 0,
+        }
+    }
+
+    /// The current scale factor.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Scales a loss value before the backward pass.
+    pub fn scale_loss(&self, loss: f64) -> f64 {
+        loss * self.scale
+    }
+
+    /// Unscales a gradient value before the non-finite check.
+    pub fn unscale(&self, value: f64) -> f64 {
+        value / self.scale
+    }
+
+    /// Updates the scale given whether a non-finite gradient was found this step, and returns
+    /// whether the optimizer step should be taken.
+    pub fn update(&mut self, found_inf: bool) -> bool {
+        if found_inf {
+            self.scale = (self.scale * 0.5).max(1.0);
+            self.successful_steps = 0;
+            false
+        } else {
+            self.successful_steps += 1;
+
+            if self.successful_steps >= self.growth_interval {
+                self.scale = (self.scale * 2.0).min(self.max_scale);
+                self.successful_steps = 0;
+            }
+
+            true
+        }
+    }
+}
+
+/// Unscales every gradient in `grads` by `scale` and reports whether any of them is non-finite,
+/// as required before the optimizer step of a mixed-precision training loop.
+pub fn unscale_and_check<B: Backend, M: Module<B>>(
+    model: &M,
+    grads: GradientsParams,
+    scale: f64,
+) -> (GradientsParams, bool) {
+    let mut visitor = UnscaleVisitor::<B> {
+        grads_in: grads,
+        grads_out: GradientsParams::new(),
+        scale,
+        found_inf: false,
+        _backend: std::marker::PhantomData,
+    };
+    model.visit(&mut visitor);
+
+    (visitor.grads_out, visitor.found_inf)
+}
+
+struct UnscaleVisitor<B: Backend> {
+    grads_in: GradientsParams,
+    grads_out: GradientsParams,
+    scale: f64,
+    found_inf: bool,
+    #[allow(dead_code)]
+    _backend: std::marker::PhantomData<B>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for UnscaleVisitor<B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads_in.get::<B, D>(id) else {
+            return;
+        };
+
+        let unscaled = grad / self.scale;
+        if !is_finite(&unscaled) {
+            self.found_inf = true;
+        }
+        self.grads_out.register::<B, D>(id.clone(), unscaled);
+    }
+}
+
+fn is_finite<B: Backend, const D: usize>(tensor: &Tensor<B, D>) -> bool {
+    tensor
+        .to_data()
+        .value
+        .iter()
+        .all(|value| value.to_f64().is_finite())
+}
@@ -0,0 +1,325 @@
+use super::amp::{self, GradScaler};
+use super::early_stopping::EarlyStoppingStrategy;
+use super::powersgd::{self, PowerSgdCompressor};
+use super::swa::SwaState;
+use crate::checkpoint::{BestCheckpointerHandle, Checkpointer};
+use crate::logger::MetricLogger;
+use crate::metric::LearningRateMetric;
+use burn_core::lr_scheduler::LRScheduler;
+use burn_core::module::{ADModule, Module, ModuleVisitor, ParamId};
+use burn_core::optim::{GradientsParams, Optimizer};
+use burn_core::tensor::backend::{ADBackend, Backend};
+use burn_core::tensor::Tensor;
+use burn_core::LearningRate;
+use std::sync::Arc;
+
+/// A single training step: given the current model and a batch, returns the gradients to apply
+/// and an output that can be fed to the train metrics through
+/// [Adaptor](crate::metric::Adaptor).
+pub trait TrainStep<M, TI, TO> {
+    /// Runs the forward and backward pass for `item` and returns the resulting gradients.
+    ///
+    /// `loss_scale` is `1.0` unless [amp](super::builder::LearnerBuilder::amp) is enabled, in
+    /// which case the loss must be multiplied by it before `backward` to avoid gradient
+    /// underflow in reduced precision; the learner divides it back out before the optimizer step.
+    fn step(&self, model: &M, item: TI, loss_scale: f64) -> (GradientsParams, TO);
+}
+
+/// A single validation step: given the current model and a batch, returns an output that can be
+/// fed to the valid metrics through [Adaptor](crate::metric::Adaptor).
+pub trait ValidStep<M, VI, VO> {
+    fn step(&self, model: &M, item: VI) -> VO;
+}
+
+/// The callback fed every train/valid item, implemented by the
+/// [Dashboard](crate::metric::dashboard::Dashboard) and the
+/// [AsyncTrainerCallback](crate::AsyncTrainerCallback) that wraps it.
+pub trait TrainerCallback<T, V>: Send {
+    /// Updates the registered train metrics with `item` and dispatches it to the renderer.
+    fn on_train_item(&mut self, item: T);
+    /// Updates the registered valid metrics with `item` and dispatches it to the renderer.
+    fn on_valid_item(&mut self, item: V);
+    /// Flushes the epoch's aggregated metrics to the loggers and renderer.
+    fn on_epoch_end(&mut self, epoch: usize);
+    /// The aggregated value of `metric_name`, on either split, for `epoch`, if it was registered.
+    fn epoch_metric(&self, epoch: usize, metric_name: &str) -> Option<f64>;
+}
+
+/// The model(s) produced by a completed [fit](Learner::fit) call.
+pub struct LearnerOutput<M> {
+    /// The model as updated by the optimizer over training, independent of SWA averaging.
+    pub model: M,
+    /// The stochastic weight averaging snapshot, if
+    /// [swa](super::builder::LearnerBuilder::swa) was enabled and at least one snapshot was taken.
+    ///
+    /// Its batch-norm running statistics are **not** recomputed against the training data: doing
+    /// so would need the model's forward pass to hand back updated running buffers, which this
+    /// crate's [TrainStep] doesn't expose. If the model contains batch norm, recompute them
+    /// manually before using it for inference.
+    pub swa_model: Option<M>,
+}
+
+/// A model, optimizer, scheduler and their surrounding training configuration, created by
+/// [LearnerBuilder](super::builder::LearnerBuilder).
+pub struct Learner<B, M, O, S, T, V>
+where
+    B: ADBackend,
+    M: ADModule<B>,
+    O: Optimizer<M, B>,
+    S: LRScheduler,
+{
+    pub(crate) model: M,
+    pub(crate) optim: O,
+    pub(crate) lr_scheduler: S,
+    pub(crate) num_epochs: usize,
+    pub(crate) callback: Box<dyn TrainerCallback<T, V>>,
+    pub(crate) checkpoint: Option<usize>,
+    pub(crate) checkpointer_model: Option<Box<dyn Checkpointer<M::Record>>>,
+    pub(crate) checkpointer_optimizer: Option<Box<dyn Checkpointer<O::Record>>>,
+    pub(crate) checkpointer_scheduler: Option<Box<dyn Checkpointer<S::Record>>>,
+    pub(crate) grad_accumulation: Option<usize>,
+    pub(crate) devices: Vec<B::Device>,
+    pub(crate) early_stopping: Option<EarlyStoppingStrategy>,
+    pub(crate) checkpointer_metric: Option<String>,
+    pub(crate) checkpointer_best: Vec<Arc<dyn BestCheckpointerHandle>>,
+    pub(crate) amp: Option<GradScaler>,
+    pub(crate) gradient_compression: Option<PowerSgdCompressor<B>>,
+    pub(crate) swa: Option<SwaState<B, M>>,
+    pub(crate) learning_rate_logger: Option<(LearningRateMetric, Box<dyn MetricLogger>)>,
+}
+
+impl<B, M, O, S, T, V> Learner<B, M, O, S, T, V>
+where
+    B: ADBackend,
+    M: ADModule<B> + Clone,
+    O: Optimizer<M, B> + Clone,
+    S: LRScheduler + Clone,
+    T: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    /// Runs the full training loop and returns the trained model, alongside its SWA average if
+    /// [swa](super::builder::LearnerBuilder::swa) is enabled.
+    ///
+    /// `dataloader_train`/`dataloader_valid` are called once per epoch and must yield that
+    /// epoch's items; `train_step`/`valid_step` turn a batch into the gradients/output consumed
+    /// by the rest of the loop.
+    pub fn fit<TI, VI, TO, VO>(
+        mut self,
+        mut dataloader_train: impl FnMut() -> Vec<TI>,
+        mut dataloader_valid: impl FnMut() -> Vec<VI>,
+        train_step: impl TrainStep<M, TI, TO>,
+        valid_step: impl ValidStep<M, VI, VO>,
+    ) -> LearnerOutput<M>
+    where
+        T: From<TO>,
+        V: From<VO>,
+    {
+        self.restore_checkpoint();
+        let starting_epoch = self.checkpoint.map(|epoch| epoch + 1).unwrap_or(1);
+        let accumulation = self.grad_accumulation.unwrap_or(1).max(1);
+
+        for epoch in starting_epoch..=self.num_epochs {
+            let mut accumulated: Option<GradientsParams> = None;
+            let mut accumulated_count = 0usize;
+
+            for item in dataloader_train() {
+                let loss_scale = self.amp.as_ref().map(GradScaler::scale).unwrap_or(1.0);
+                let (grads, output) = train_step.step(&self.model, item, loss_scale);
+                self.callback.on_train_item(T::from(output));
+
+                accumulated = Some(match accumulated.take() {
+                    None => grads,
+                    Some(sum) => add_gradients(&self.model, sum, grads),
+                });
+                accumulated_count += 1;
+
+                if accumulated_count < accumulation {
+                    continue;
+                }
+
+                let grads = accumulated
+                    .take()
+                    .expect("just accumulated at least one gradient this step");
+                accumulated_count = 0;
+
+                let lr = self.lr_scheduler.step();
+                self.optimizer_step(epoch, lr, grads, loss_scale);
+
+                if let Some(swa) = self.swa.as_mut() {
+                    swa.step(epoch, &self.model);
+                }
+            }
+
+            // Flush a partial accumulation window left over at the end of the epoch instead of
+            // silently dropping it; the learning rate it steps with is the one that would have
+            // applied to its (never reached) final item.
+            if let Some(grads) = accumulated.take() {
+                let loss_scale = self.amp.as_ref().map(GradScaler::scale).unwrap_or(1.0);
+                let lr = self.lr_scheduler.step();
+                self.optimizer_step(epoch, lr, grads, loss_scale);
+
+                if let Some(swa) = self.swa.as_mut() {
+                    swa.step(epoch, &self.model);
+                }
+            }
+
+            for item in dataloader_valid() {
+                let output = valid_step.step(&self.model, item);
+                self.callback.on_valid_item(V::from(output));
+            }
+
+            self.callback.on_epoch_end(epoch);
+            self.save_checkpoint(epoch);
+            self.rank_checkpoint(epoch);
+
+            if self.should_stop(epoch) {
+                break;
+            }
+        }
+
+        LearnerOutput {
+            swa_model: self.swa.take().and_then(SwaState::into_model),
+            model: self.model,
+        }
+    }
+
+    /// Applies the gradients for one training step: compresses them through
+    /// [gradient_compression_powersgd](super::builder::LearnerBuilder::gradient_compression_powersgd)
+    /// if enabled, then unscales them and skips the step entirely on a non-finite gradient if
+    /// [amp](super::builder::LearnerBuilder::amp) is enabled.
+    fn optimizer_step(
+        &mut self,
+        _epoch: usize,
+        lr: LearningRate,
+        grads: GradientsParams,
+        loss_scale: f64,
+    ) {
+        let grads = if let Some(compressor) = self.gradient_compression.as_mut() {
+            compressor.step();
+            powersgd::compress_gradients(&self.model, grads, compressor)
+        } else {
+            grads
+        };
+
+        let should_step = if let Some(scaler) = self.amp.as_mut() {
+            let (unscaled, found_inf) = amp::unscale_and_check(&self.model, grads, loss_scale);
+            let should_step = scaler.update(found_inf);
+            if should_step {
+                self.model = self.optim.step(lr, self.model.clone(), unscaled);
+            }
+            should_step
+        } else {
+            self.model = self.optim.step(lr, self.model.clone(), grads);
+            true
+        };
+
+        if should_step {
+            if let Some((metric, logger)) = self.learning_rate_logger.as_mut() {
+                let entry = metric.update(&lr, &Default::default());
+                logger.log(&entry);
+            }
+        }
+    }
+
+    fn restore_checkpoint(&mut self) {
+        let Some(epoch) = self.checkpoint else {
+            return;
+        };
+
+        if let Some(checkpointer) = &self.checkpointer_model {
+            if let Ok(record) = checkpointer.restore(epoch) {
+                self.model = self.model.clone().load_record(record);
+            }
+        }
+        if let Some(checkpointer) = &self.checkpointer_optimizer {
+            if let Ok(record) = checkpointer.restore(epoch) {
+                self.optim = self.optim.clone().load_record(record);
+            }
+        }
+        if let Some(checkpointer) = &self.checkpointer_scheduler {
+            if let Ok(record) = checkpointer.restore(epoch) {
+                self.lr_scheduler = self.lr_scheduler.clone().load_record(record);
+            }
+        }
+    }
+
+    fn save_checkpoint(&self, epoch: usize) {
+        if let Some(checkpointer) = &self.checkpointer_model {
+            let _ = checkpointer.save(epoch, self.model.clone().into_record());
+        }
+        if let Some(checkpointer) = &self.checkpointer_optimizer {
+            let _ = checkpointer.save(epoch, self.optim.clone().into_record());
+        }
+        if let Some(checkpointer) = &self.checkpointer_scheduler {
+            let _ = checkpointer.save(epoch, self.lr_scheduler.clone().into_record());
+        }
+    }
+
+    /// Ranks this epoch's checkpoint against the ones already kept and deletes whichever one
+    /// falls out of the top `num_keep`, per [with_file_checkpointer_best](super::builder::LearnerBuilder::with_file_checkpointer_best).
+    fn rank_checkpoint(&self, epoch: usize) {
+        let Some(metric_name) = &self.checkpointer_metric else {
+            return;
+        };
+        let Some(value) = self.callback.epoch_metric(epoch, metric_name) else {
+            return;
+        };
+
+        for checkpointer in &self.checkpointer_best {
+            if let Some(evicted) = checkpointer.register(epoch, value) {
+                checkpointer.delete(evicted);
+            }
+        }
+    }
+
+    /// Updates early stopping with this epoch's monitored metric and returns whether training
+    /// should stop.
+    fn should_stop(&mut self, epoch: usize) -> bool {
+        let Some(early_stopping) = self.early_stopping.as_mut() else {
+            return false;
+        };
+        let Some(value) = self.callback.epoch_metric(epoch, early_stopping.metric_name()) else {
+            return false;
+        };
+
+        early_stopping.update(value)
+    }
+}
+
+/// Sums two gradient bags parameter-by-parameter, as
+/// [grads_accumulation](super::builder::LearnerBuilder::grads_accumulation) requires: the
+/// optimizer must see the sum of every backward pass folded into an accumulation window, not just
+/// the last one.
+fn add_gradients<B: Backend, M: Module<B>>(
+    model: &M,
+    a: GradientsParams,
+    b: GradientsParams,
+) -> GradientsParams {
+    let mut visitor = SumVisitor {
+        a,
+        b,
+        out: GradientsParams::new(),
+    };
+    model.visit(&mut visitor);
+
+    visitor.out
+}
+
+struct SumVisitor<B: Backend> {
+    a: GradientsParams,
+    b: GradientsParams,
+    out: GradientsParams,
+}
+
+impl<B: Backend> ModuleVisitor<B> for SumVisitor<B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        let sum = match (self.a.get::<B, D>(id), self.b.get::<B, D>(id)) {
+            (Some(a), Some(b)) => a + b,
+            (Some(a), None) => a,
+            (None, Some(b)) => b,
+            (None, None) => return,
+        };
+
+        self.out.register::<B, D>(id.clone(), sum);
+    }
+}
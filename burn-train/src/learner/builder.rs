@@ -1,10 +1,17 @@
+use super::amp::GradScaler;
+use super::early_stopping::{EarlyStoppingStrategy, MetricEarlyStoppingMode};
 use super::log::update_log_file;
+use super::powersgd::PowerSgdCompressor;
+use super::swa::SwaState;
 use super::Learner;
-use crate::checkpoint::{AsyncCheckpointer, Checkpointer, FileCheckpointer};
+use crate::checkpoint::{
+    AsyncCheckpointer, BestCheckpointerHandle, BestFileCheckpointer, Checkpointer, CheckpointingMode,
+    FileCheckpointer,
+};
 use crate::logger::{FileMetricLogger, MetricLogger};
 use crate::metric::dashboard::cli::CLIDashboardRenderer;
 use crate::metric::dashboard::Dashboard;
-use crate::metric::{Adaptor, Metric, Numeric};
+use crate::metric::{Adaptor, LearningRateMetric, Metric, Numeric};
 use crate::AsyncTrainerCallback;
 use burn_core::lr_scheduler::LRScheduler;
 use burn_core::module::ADModule;
@@ -14,6 +21,44 @@ use burn_core::tensor::backend::ADBackend;
 
 use std::sync::Arc;
 
+/// Returns the highest epoch found under `{directory}/checkpoint` for which the model,
+/// optimizer and scheduler record files all decode successfully, or `None` if no such epoch
+/// exists. This guards against a crash leaving a partial checkpoint behind, since the three
+/// record files are written asynchronously.
+fn find_latest_checkpoint<M, O, S>(
+    directory: &str,
+    checkpointer_model: &dyn Checkpointer<M>,
+    checkpointer_optimizer: &dyn Checkpointer<O>,
+    checkpointer_scheduler: &dyn Checkpointer<S>,
+) -> Option<usize> {
+    let mut epochs = list_checkpoint_epochs(format!("{directory}/checkpoint").as_str(), "model");
+    epochs.sort_unstable_by(|a, b| b.cmp(a));
+
+    epochs.into_iter().find(|&epoch| {
+        checkpointer_model.restore(epoch).is_ok()
+            && checkpointer_optimizer.restore(epoch).is_ok()
+            && checkpointer_scheduler.restore(epoch).is_ok()
+    })
+}
+
+/// Lists the epoch numbers of checkpoint files named `{prefix}-{epoch}.*` under `directory`.
+fn list_checkpoint_epochs(directory: &str, prefix: &str) -> Vec<usize> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| {
+            name.strip_prefix(&format!("{prefix}-"))
+                .and_then(|rest| rest.split('.').next())
+                .and_then(|epoch| epoch.parse::<usize>().ok())
+        })
+        .collect()
+}
+
 /// Struct to configure and create a [learner](Learner).
 pub struct LearnerBuilder<B, T, V, M, O, S>
 where
@@ -33,6 +78,14 @@ where
     directory: String,
     grad_accumulation: Option<usize>,
     devices: Vec<B::Device>,
+    early_stopping: Option<EarlyStoppingStrategy>,
+    checkpointer_metric: Option<String>,
+    checkpointer_best: Vec<Arc<dyn BestCheckpointerHandle>>,
+    resume_from_latest: bool,
+    amp: Option<GradScaler>,
+    gradient_compression: Option<PowerSgdCompressor<B>>,
+    swa: Option<SwaState<B, M>>,
+    log_learning_rate: bool,
 }
 
 impl<B, T, V, Model, Optim, LR> LearnerBuilder<B, T, V, Model, Optim, LR>
@@ -64,6 +117,14 @@ where
             directory: directory.to_string(),
             grad_accumulation: None,
             devices: vec![B::Device::default()],
+            early_stopping: None,
+            checkpointer_metric: None,
+            checkpointer_best: Vec::new(),
+            resume_from_latest: false,
+            amp: None,
+            gradient_compression: None,
+            swa: None,
+            log_learning_rate: false,
         }
     }
 
@@ -116,6 +177,25 @@ where
         self
     }
 
+    /// Enable mixed-precision training with dynamic loss scaling.
+    ///
+    /// The learner does not change the precision of the model or of its forward and backward
+    /// passes; it only manages the loss scale. The current scale, starting at `init_scale`, is
+    /// passed to [TrainStep](super::TrainStep::step), which multiplies the loss by it before
+    /// `backward` to avoid gradient underflow; before the optimizer step, the gradients are unscaled and
+    /// checked for non-finite values. If any are found, the step is skipped and the scale is
+    /// halved; otherwise, after `growth_interval` consecutive successful steps, the scale is
+    /// doubled, capped at `max_scale`.
+    ///
+    /// # Notes
+    ///
+    /// When combined with [grads_accumulation](Self::grads_accumulation), unscaling happens on
+    /// the accumulated gradient, once per optimizer step rather than once per backward pass.
+    pub fn amp(mut self, init_scale: f64, growth_interval: usize, max_scale: f64) -> Self {
+        self.amp = Some(GradScaler::new(init_scale, growth_interval, max_scale));
+        self
+    }
+
     /// Register a training metric and displays it on a plot.
     ///
     /// # Notes
@@ -147,6 +227,41 @@ where
         self
     }
 
+    /// Produce an averaged model via stochastic weight averaging, which often generalizes better
+    /// than the final iterate.
+    ///
+    /// Once the current epoch reaches `start_epoch`, a running average of the model's weights is
+    /// maintained, updated every `update_period` iterations. Both the trained and the averaged
+    /// model are returned by [Learner::fit], in its [LearnerOutput](super::LearnerOutput). The
+    /// averaged model's batch-norm running statistics are not recomputed against the training
+    /// data.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_epoch` - The epoch at which averaging begins.
+    /// * `update_period` - The number of iterations between snapshots.
+    pub fn swa(mut self, start_epoch: usize, update_period: usize) -> Self {
+        self.swa = Some(SwaState::new(start_epoch, update_period));
+        self
+    }
+
+    /// Record the current learning rate produced by the [LR scheduler](LRScheduler) to the train
+    /// metrics file, alongside loss and accuracy.
+    ///
+    /// This is especially useful for cyclic or one-cycle schedules, where the learning-rate
+    /// trajectory matters as much as the loss curve.
+    ///
+    /// # Notes
+    ///
+    /// This only writes the value to the train [metric logger](MetricLogger); it is not plotted
+    /// live on the [Dashboard], since the dashboard's plots are driven by the user's train item
+    /// type `T` through [Adaptor], and the learning rate isn't derivable from it. Read it back
+    /// from the logged file to chart it after the fact.
+    pub fn log_learning_rate(mut self) -> Self {
+        self.log_learning_rate = true;
+        self
+    }
+
     /// The number of epochs the training should last.
     pub fn num_epochs(mut self, num_epochs: usize) -> Self {
         self.num_epochs = num_epochs;
@@ -159,12 +274,65 @@ where
         self
     }
 
+    /// Compress gradients with a PowerSGD-style low-rank factorization, with error feedback
+    /// carried across steps.
+    ///
+    /// [Learner::fit] hands each gradient to the compressor as a single device's gradient, so it
+    /// is compressed and reconstructed locally; no gradients are exchanged across
+    /// [devices](Self::devices) during training.
+    ///
+    /// Rank-1 gradients (biases, norms) bypass compression and are averaged as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the low-rank factorization used to compress each gradient.
+    /// * `warm_start_steps` - The number of initial steps that bypass compression.
+    pub fn gradient_compression_powersgd(mut self, rank: usize, warm_start_steps: usize) -> Self {
+        self.gradient_compression = Some(PowerSgdCompressor::new(rank, warm_start_steps));
+        self
+    }
+
     /// The epoch from which the training must resume.
     pub fn checkpoint(mut self, checkpoint: usize) -> Self {
         self.checkpoint = Some(checkpoint);
         self
     }
 
+    /// Resume from the latest valid checkpoint found on disk, if any, instead of requiring the
+    /// epoch to be passed explicitly through [checkpoint](Self::checkpoint).
+    ///
+    /// This makes crash-restart workflows seamless: the same launch command either starts fresh
+    /// or continues from where it left off, without the caller tracking epoch numbers.
+    pub fn resume_from_latest(mut self) -> Self {
+        self.resume_from_latest = true;
+        self
+    }
+
+    /// Stop training early when a monitored metric stops improving.
+    ///
+    /// The metric must be one already registered through [metric_train](Self::metric_train) or
+    /// [metric_valid](Self::metric_valid). After each epoch, the aggregated value is read from
+    /// the dashboard and compared against the best value seen so far; if it fails to improve by
+    /// at least `min_delta` for `patience` consecutive epochs, the training loop stops before
+    /// `num_epochs` is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric_name` - The name of the metric to monitor.
+    /// * `mode` - Whether the metric should be minimized or maximized.
+    /// * `min_delta` - The minimum change to count as an improvement.
+    /// * `patience` - The number of epochs to wait for an improvement before stopping.
+    pub fn early_stopping(
+        mut self,
+        metric_name: &str,
+        mode: MetricEarlyStoppingMode,
+        min_delta: f64,
+        patience: usize,
+    ) -> Self {
+        self.early_stopping = Some(EarlyStoppingStrategy::new(metric_name, mode, min_delta, patience));
+        self
+    }
+
     /// Register a checkpointer that will save the [optimizer](Optimizer) and the
     /// [model](ADModule).
     ///
@@ -196,6 +364,70 @@ where
         self
     }
 
+    /// Register a checkpointer that keeps the `num_keep` best checkpoints, ranked by a monitored
+    /// metric, instead of the most recent ones.
+    ///
+    /// The metric must be one already registered through [metric_train](Self::metric_train) or
+    /// [metric_valid](Self::metric_valid). After each epoch, the aggregated value is read from
+    /// the dashboard and used to rank the epoch's checkpoint; once more than `num_keep`
+    /// checkpoints are kept, the worst ranked one is deleted.
+    pub fn with_file_checkpointer_best<FR>(
+        mut self,
+        num_keep: usize,
+        recorder: FR,
+        metric_name: &str,
+        mode: CheckpointingMode,
+    ) -> Self
+    where
+        FR: FileRecorder + 'static,
+    {
+        // Eviction is driven by `BestFileCheckpointer` itself, so the wrapped file checkpointers
+        // must never evict by age on their own.
+        let checkpointer_model = Arc::new(BestFileCheckpointer::new(
+            FileCheckpointer::new(
+                recorder.clone(),
+                format!("{}/checkpoint", self.directory).as_str(),
+                "model",
+                usize::MAX,
+            ),
+            mode,
+            num_keep,
+        ));
+        let checkpointer_optimizer = Arc::new(BestFileCheckpointer::new(
+            FileCheckpointer::new(
+                recorder.clone(),
+                format!("{}/checkpoint", self.directory).as_str(),
+                "optim",
+                usize::MAX,
+            ),
+            mode,
+            num_keep,
+        ));
+        let checkpointer_scheduler = Arc::new(BestFileCheckpointer::new(
+            FileCheckpointer::new(
+                recorder,
+                format!("{}/checkpoint", self.directory).as_str(),
+                "scheduler",
+                usize::MAX,
+            ),
+            mode,
+            num_keep,
+        ));
+
+        // Kept separately, and type-erased over the metric value alone rather than the record
+        // type, so the epoch loop can rank and evict checkpoints across all three at once.
+        self.checkpointer_best = vec![
+            checkpointer_model.clone() as Arc<dyn BestCheckpointerHandle>,
+            checkpointer_optimizer.clone() as Arc<dyn BestCheckpointerHandle>,
+            checkpointer_scheduler.clone() as Arc<dyn BestCheckpointerHandle>,
+        ];
+        self.checkpointer_model = Some(checkpointer_model);
+        self.checkpointer_optimizer = Some(checkpointer_optimizer);
+        self.checkpointer_scheduler = Some(checkpointer_scheduler);
+        self.checkpointer_metric = Some(metric_name.to_string());
+        self
+    }
+
     /// Create the [learner](Learner) from a [model](ADModule) and an [optimizer](Optimizer).
     /// The [learning rate scheduler](LRScheduler) can also be a simple
     /// [learning rate](burn_core::LearningRate).
@@ -214,6 +446,16 @@ where
         let callback = Box::new(self.dashboard);
         let callback = Box::new(AsyncTrainerCallback::new(callback));
 
+        // The learning rate isn't derivable from the user's train item `T`, so it can't go
+        // through `Dashboard::register_train_plot`, which requires `T: Adaptor<M::Input>`.
+        // Feed it straight to a dedicated logger instead, alongside the train metrics file.
+        let learning_rate_logger: Option<(LearningRateMetric, Box<dyn MetricLogger>)> = if self.log_learning_rate {
+            let logger = Box::new(FileMetricLogger::new(format!("{}/train", self.directory).as_str()));
+            Some((LearningRateMetric::new(), logger))
+        } else {
+            None
+        };
+
         let checkpointer_optimizer = match self.checkpointer_optimizer {
             Some(checkpointer) => {
                 let checkpointer: Box<dyn Checkpointer<Optim::Record>> =
@@ -239,18 +481,39 @@ where
             None => None,
         };
 
+        let checkpoint = if self.resume_from_latest {
+            match (&checkpointer_model, &checkpointer_optimizer, &checkpointer_scheduler) {
+                (Some(model), Some(optim), Some(scheduler)) => find_latest_checkpoint(
+                    self.directory.as_str(),
+                    model.as_ref(),
+                    optim.as_ref(),
+                    scheduler.as_ref(),
+                ),
+                _ => None,
+            }
+        } else {
+            self.checkpoint
+        };
+
         Learner {
             model,
             optim,
             lr_scheduler,
             num_epochs: self.num_epochs,
             callback,
-            checkpoint: self.checkpoint,
+            checkpoint,
             checkpointer_model,
             checkpointer_optimizer,
             checkpointer_scheduler,
             grad_accumulation: self.grad_accumulation,
             devices: self.devices,
+            early_stopping: self.early_stopping,
+            checkpointer_metric: self.checkpointer_metric,
+            checkpointer_best: self.checkpointer_best,
+            amp: self.amp,
+            gradient_compression: self.gradient_compression,
+            swa: self.swa,
+            learning_rate_logger,
         }
     }
 
@@ -0,0 +1,119 @@
+/// The direction a monitored metric should move in to be considered an improvement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricEarlyStoppingMode {
+    /// The metric should decrease (e.g. loss).
+    Min,
+    /// The metric should increase (e.g. accuracy).
+    Max,
+}
+
+/// Stops training when a monitored metric has stopped improving.
+///
+/// The metric is read from the [dashboard](crate::metric::dashboard::Dashboard) after each epoch.
+/// An improvement is only recorded when the new value beats the best value seen so far by at
+/// least `min_delta`, following the direction given by [mode](MetricEarlyStoppingMode). Once
+/// `patience` consecutive epochs pass without an improvement, [update](Self::update) returns
+/// `true`.
+pub struct EarlyStoppingStrategy {
+    metric_name: String,
+    mode: MetricEarlyStoppingMode,
+    min_delta: f64,
+    patience: usize,
+    best: Option<f64>,
+    num_epochs_since_best: usize,
+}
+
+impl EarlyStoppingStrategy {
+    /// Creates a new early stopping strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric_name` - The name of a metric registered on the train or valid split.
+    /// * `mode` - Whether the metric should be minimized or maximized.
+    /// * `min_delta` - The minimum change to count as an improvement.
+    /// * `patience` - The number of epochs to wait for an improvement before stopping.
+    pub fn new(metric_name: &str, mode: MetricEarlyStoppingMode, min_delta: f64, patience: usize) -> Self {
+        Self {
+            metric_name: metric_name.to_string(),
+            mode,
+            min_delta,
+            patience,
+            best: None,
+            num_epochs_since_best: 0,
+        }
+    }
+
+    /// The name of the metric being monitored.
+    pub fn metric_name(&self) -> &str {
+        &self.metric_name
+    }
+
+    /// Updates the strategy with the metric value recorded for the epoch and returns `true` when
+    /// training should stop.
+    pub fn update(&mut self, current: f64) -> bool {
+        let improved = match self.best {
+            None => true,
+            Some(best) => match self.mode {
+                MetricEarlyStoppingMode::Min => current < best - self.min_delta,
+                MetricEarlyStoppingMode::Max => current > best + self.min_delta,
+            },
+        };
+
+        if improved {
+            self.best = Some(current);
+            self.num_epochs_since_best = 0;
+        } else {
+            self.num_epochs_since_best += 1;
+        }
+
+        self.num_epochs_since_best >= self.patience
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_update_is_always_an_improvement() {
+        let mut strategy = EarlyStoppingStrategy::new("loss", MetricEarlyStoppingMode::Min, 0.0, 1);
+
+        assert!(!strategy.update(10.0));
+    }
+
+    #[test]
+    fn min_mode_stops_once_patience_is_exhausted_without_improvement() {
+        let mut strategy = EarlyStoppingStrategy::new("loss", MetricEarlyStoppingMode::Min, 0.0, 2);
+
+        assert!(!strategy.update(1.0));
+        assert!(!strategy.update(1.0));
+        assert!(strategy.update(1.0));
+    }
+
+    #[test]
+    fn min_mode_resets_patience_on_improvement() {
+        let mut strategy = EarlyStoppingStrategy::new("loss", MetricEarlyStoppingMode::Min, 0.0, 2);
+
+        assert!(!strategy.update(1.0));
+        assert!(!strategy.update(0.5));
+        assert!(!strategy.update(0.5));
+        assert!(strategy.update(0.5));
+    }
+
+    #[test]
+    fn max_mode_treats_a_larger_value_as_an_improvement() {
+        let mut strategy = EarlyStoppingStrategy::new("accuracy", MetricEarlyStoppingMode::Max, 0.0, 1);
+
+        assert!(!strategy.update(0.5));
+        assert!(!strategy.update(0.6));
+        assert!(strategy.update(0.6));
+    }
+
+    #[test]
+    fn min_delta_requires_a_margin_to_count_as_an_improvement() {
+        let mut strategy = EarlyStoppingStrategy::new("loss", MetricEarlyStoppingMode::Min, 0.1, 1);
+
+        assert!(!strategy.update(1.0));
+        assert!(strategy.update(0.95));
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use burn_core::module::{Module, ModuleVisitor, ParamId};
+use burn_core::optim::GradientsParams;
+use burn_core::tensor::{backend::Backend, Distribution, Tensor};
+
+/// Per-parameter state kept across steps: the persistent low-rank basis `q`, shared by every
+/// device, and each device's own error-feedback residual carried over from the previous step.
+struct PowerSgdState<B: Backend> {
+    q: Tensor<B, 2>,
+    errors: Vec<Tensor<B, 2>>,
+}
+
+/// PowerSGD-style low-rank gradient compressor for multi-device training.
+///
+/// Instead of all-reducing a full gradient matrix across devices, only a small rank-`r`
+/// factorization of it is communicated, in two rounds: first the left factor `P` (shape `n x r`),
+/// then the right factor `Q` (shape `m x r`), each averaged across devices. A persistent basis `q`
+/// and a per-device error-feedback buffer are maintained across steps: the buffer is folded into
+/// that device's gradient before factorizing, and the reconstruction error is carried forward so
+/// that it gets a chance to be compressed on a later step rather than being discarded. Rank-1
+/// gradients (biases, norms) fall back to plain averaging instead: reshaped to an `n x 1` matrix,
+/// they're already rank <= 1, so factorizing them at any `rank > 1` would have Gram-Schmidt
+/// project every column but the first onto itself, collapsing it to near-zero norm.
+pub struct PowerSgdCompressor<B: Backend> {
+    rank: usize,
+    warm_start_steps: usize,
+    step: usize,
+    state: Vec<Option<PowerSgdState<B>>>,
+    indices: HashMap<ParamId, usize>,
+}
+
+impl<B: Backend> PowerSgdCompressor<B> {
+    /// Creates a new compressor.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the low-rank factorization used to compress each gradient.
+    /// * `warm_start_steps` - The number of initial steps that bypass compression, allowing
+    ///   parameters to settle before their gradients are approximated.
+    pub fn new(rank: usize, warm_start_steps: usize) -> Self {
+        Self {
+            rank,
+            warm_start_steps,
+            step: 0,
+            state: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    /// Advances the step counter. Must be called exactly once per optimizer step, no matter how
+    /// many parameters are compressed during that step, since `warm_start_steps` counts optimizer
+    /// steps rather than `compress` calls.
+    pub fn step(&mut self) {
+        self.step += 1;
+    }
+
+    /// Returns the stable per-parameter index `compress` expects, assigning the next free one the
+    /// first time `id` is seen. Relies on a module's parameters being visited in the same order
+    /// every step, which holds as long as its structure doesn't change between calls.
+    fn index_of(&mut self, id: &ParamId) -> usize {
+        let next = self.indices.len();
+        *self.indices.entry(id.clone()).or_insert(next)
+    }
+
+    /// Compresses the gradient for a single parameter, reshaping it to a 2-D matrix first so
+    /// parameters of any rank can go through the same low-rank factorization, as required to wire
+    /// this compressor into a training loop that only sees type-erased gradients. Rank-1 gradients
+    /// (biases, norms), and any gradient that reshapes to a single-column matrix, bypass
+    /// compression and are returned unchanged, since a single column can't be factorized at
+    /// `rank > 1` without collapsing under Gram-Schmidt. See [compress_gradients] for the generic
+    /// entry point used by [Learner](super::Learner).
+    pub fn compress_param<const D: usize>(
+        &mut self,
+        id: &ParamId,
+        grad: Tensor<B, D>,
+        device: &B::Device,
+    ) -> Tensor<B, D> {
+        let dims = grad.dims();
+        let rows = dims[0];
+        let cols = dims[1..].iter().product::<usize>().max(1);
+
+        if dims.len() == 1 || cols == 1 {
+            return grad;
+        }
+
+        let flat = grad.reshape([rows, cols]);
+        let index = self.index_of(id);
+        let compressed = self.compress(index, &[flat], device);
+
+        compressed.reshape(dims)
+    }
+
+    /// Compresses and reconstructs the 2-D gradient registered under `index`, all-reducing the
+    /// low-rank factors across `grads` (one entry per device holding that parameter) instead of
+    /// the full gradient. `index` must identify the same parameter, with the same shape, on every
+    /// call, and `grads` must be ordered consistently across calls so each position keeps its own
+    /// error-feedback buffer.
+    pub fn compress(&mut self, index: usize, grads: &[Tensor<B, 2>], device: &B::Device) -> Tensor<B, 2> {
+        assert!(!grads.is_empty(), "compress requires at least one device gradient");
+
+        if self.step <= self.warm_start_steps {
+            return average(grads);
+        }
+
+        if self.state.len() <= index {
+            self.state.resize_with(index + 1, || None);
+        }
+
+        let [n, m] = grads[0].dims();
+        let num_devices = grads.len();
+        let state = self.state[index].get_or_insert_with(|| PowerSgdState {
+            q: Tensor::random([m, self.rank], Distribution::Normal(0.0, 1.0), device),
+            errors: (0..num_devices).map(|_| Tensor::zeros([n, m], device)).collect(),
+        });
+
+        // Error feedback: carry each device's residual dropped by the previous step forward.
+        let m_fed: Vec<Tensor<B, 2>> = grads
+            .iter()
+            .zip(state.errors.iter())
+            .map(|(grad, error)| grad.clone() + error.clone())
+            .collect();
+
+        // Round 1: all-reduce the left factor P (shape n x rank).
+        let p_local: Vec<Tensor<B, 2>> = m_fed
+            .iter()
+            .map(|fed| orthonormalize(fed.clone().matmul(state.q.clone())))
+            .collect();
+        let p = average(&p_local);
+
+        // Round 2: all-reduce the right factor Q (shape m x rank), derived from the shared P.
+        let q_local: Vec<Tensor<B, 2>> = m_fed
+            .iter()
+            .map(|fed| fed.clone().transpose().matmul(p.clone()))
+            .collect();
+        let q = average(&q_local);
+
+        let reconstructed = p.matmul(q.clone().transpose());
+
+        state.errors = m_fed
+            .into_iter()
+            .map(|fed| fed - reconstructed.clone())
+            .collect();
+        state.q = q;
+
+        reconstructed
+    }
+}
+
+/// Averages a set of same-shaped tensors, as every round of the PowerSGD all-reduce does.
+fn average<B: Backend, const D: usize>(tensors: &[Tensor<B, D>]) -> Tensor<B, D> {
+    let count = tensors.len() as f64;
+    let mut sum = tensors[0].clone();
+
+    for tensor in &tensors[1..] {
+        sum = sum + tensor.clone();
+    }
+
+    sum / count
+}
+
+/// Runs every gradient in `grads` through `compressor`, reshaping each to a 2-D matrix so
+/// parameters of any rank are compressed uniformly. This is the entry point
+/// [Learner](super::Learner) calls from its optimizer step once `compressor.step()` has been
+/// advanced for the step.
+pub fn compress_gradients<B: Backend, M: Module<B>>(
+    model: &M,
+    grads: GradientsParams,
+    compressor: &mut PowerSgdCompressor<B>,
+) -> GradientsParams {
+    let mut visitor = CompressVisitor {
+        grads_in: grads,
+        grads_out: GradientsParams::new(),
+        compressor,
+    };
+    model.visit(&mut visitor);
+
+    visitor.grads_out
+}
+
+struct CompressVisitor<'a, B: Backend> {
+    grads_in: GradientsParams,
+    grads_out: GradientsParams,
+    compressor: &'a mut PowerSgdCompressor<B>,
+}
+
+impl<'a, B: Backend> ModuleVisitor<B> for CompressVisitor<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads_in.get::<B, D>(id) else {
+            return;
+        };
+
+        let device = tensor.device();
+        let compressed = self.compressor.compress_param(id, grad, &device);
+        self.grads_out.register::<B, D>(id.clone(), compressed);
+    }
+}
+
+/// Orthonormalizes the columns of `p` via Gram-Schmidt.
+fn orthonormalize<B: Backend>(p: Tensor<B, 2>) -> Tensor<B, 2> {
+    let rank = p.dims()[1];
+    let rows = p.dims()[0];
+    let mut columns: Vec<Tensor<B, 2>> = Vec::with_capacity(rank);
+
+    for i in 0..rank {
+        let mut column = p.clone().slice([0..rows, i..i + 1]);
+
+        for previous in columns.iter() {
+            let projection = column.clone().mul(previous.clone()).sum();
+            column = column - previous.clone() * projection;
+        }
+
+        let norm = column.clone().powf(2.0).sum().sqrt();
+        columns.push(column / norm);
+    }
+
+    Tensor::cat(columns, 1)
+}
@@ -0,0 +1,111 @@
+use burn_core::module::{Module, ModuleMapper, ModuleVisitor, ParamId};
+use burn_core::tensor::backend::Backend;
+use burn_core::tensor::Tensor;
+
+/// Flattens and collects a module's parameters, in visitation order, so they can later be
+/// recombined with a [ParameterWriter].
+struct ParameterCollector<B: Backend> {
+    tensors: Vec<Tensor<B, 1>>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for ParameterCollector<B> {
+    fn visit<const D: usize>(&mut self, _id: &ParamId, tensor: &Tensor<B, D>) {
+        let num_elements = tensor.shape().num_elements();
+        self.tensors.push(tensor.clone().reshape([num_elements]));
+    }
+}
+
+/// Rewrites a module's parameters, in visitation order, from pre-computed flattened tensors.
+struct ParameterWriter<B: Backend> {
+    tensors: std::vec::IntoIter<Tensor<B, 1>>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParameterWriter<B> {
+    fn map<const D: usize>(&mut self, _id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let shape = tensor.shape();
+        self.tensors
+            .next()
+            .expect("one averaged tensor per visited parameter")
+            .reshape(shape.dims)
+    }
+}
+
+/// Maintains a running average of a model's weights for stochastic weight averaging.
+///
+/// Once `start_epoch` is reached, the average is updated every `update_period` training
+/// iterations as `w_swa = (w_swa * n + w) / (n + 1)`, where `n` counts the snapshots folded into
+/// the average so far.
+pub struct SwaState<B: Backend, M> {
+    start_epoch: usize,
+    update_period: usize,
+    iteration: usize,
+    n: usize,
+    averaged: Option<M>,
+    _backend: std::marker::PhantomData<B>,
+}
+
+impl<B: Backend, M: Module<B> + Clone> SwaState<B, M> {
+    /// Creates a new SWA state that starts averaging at `start_epoch`, taking a snapshot every
+    /// `update_period` iterations.
+    pub fn new(start_epoch: usize, update_period: usize) -> Self {
+        Self {
+            start_epoch,
+            update_period,
+            iteration: 0,
+            n: 0,
+            averaged: None,
+            _backend: std::marker::PhantomData,
+        }
+    }
+
+    /// Called once per training iteration; folds `model` into the running average once
+    /// `start_epoch` and `update_period` are reached.
+    pub fn step(&mut self, epoch: usize, model: &M) {
+        if epoch < self.start_epoch {
+            return;
+        }
+
+        self.iteration += 1;
+        if self.iteration % self.update_period != 0 {
+            return;
+        }
+
+        self.averaged = Some(match self.averaged.take() {
+            None => model.clone(),
+            Some(running) => {
+                self.n += 1;
+                average(&running, model, self.n)
+            }
+        });
+    }
+
+    /// The averaged model, if at least one snapshot has been taken.
+    ///
+    /// Before being used for inference, its batch-norm running statistics should be recomputed
+    /// with a forward pass over the training data, since the averaged weights never directly saw
+    /// real activations.
+    pub fn into_model(self) -> Option<M> {
+        self.averaged
+    }
+}
+
+fn average<B: Backend, M: Module<B> + Clone>(running: &M, current: &M, n: usize) -> M {
+    let mut collector = ParameterCollector { tensors: Vec::new() };
+    running.clone().visit(&mut collector);
+    let running_tensors = collector.tensors;
+
+    let mut collector = ParameterCollector { tensors: Vec::new() };
+    current.clone().visit(&mut collector);
+    let current_tensors = collector.tensors;
+
+    let n = n as f64;
+    let blended = running_tensors
+        .into_iter()
+        .zip(current_tensors)
+        .map(|(running, current)| (running * n + current) / (n + 1.0))
+        .collect::<Vec<_>>();
+
+    current
+        .clone()
+        .map(&mut ParameterWriter { tensors: blended.into_iter() })
+}
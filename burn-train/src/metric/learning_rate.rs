@@ -0,0 +1,47 @@
+use super::{Metric, MetricEntry, MetricMetadata, Numeric};
+
+/// Tracks the learning rate returned by the
+/// [LR scheduler](burn_core::lr_scheduler::LRScheduler) for the current step. The learner writes
+/// it to the train metric logger; it is not plotted on the
+/// [Dashboard](crate::metric::dashboard::Dashboard).
+///
+/// Unlike most metrics, its input isn't derived from a train or valid item; the learner feeds it
+/// the scheduler's returned learning rate directly after each step.
+pub struct LearningRateMetric {
+    current: f64,
+}
+
+impl LearningRateMetric {
+    /// Creates a new learning rate metric.
+    pub fn new() -> Self {
+        Self { current: 0.0 }
+    }
+}
+
+impl Default for LearningRateMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metric for LearningRateMetric {
+    type Input = f64;
+
+    fn update(&mut self, lr: &f64, _metadata: &MetricMetadata) -> MetricEntry {
+        self.current = *lr;
+
+        MetricEntry::new(
+            "Learning Rate".to_string(),
+            format!("{:.2e}", self.current),
+            format!("{:.2e}", self.current),
+        )
+    }
+
+    fn clear(&mut self) {}
+}
+
+impl Numeric for LearningRateMetric {
+    fn value(&self) -> f64 {
+        self.current
+    }
+}